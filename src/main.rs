@@ -35,11 +35,17 @@
 //  material.
 
 use std::io;
+use std::io::Read;
 
+use anyhow::{Context, Result};
+use bio::io::fasta;
 use clap::Parser;
+use clap::ValueEnum;
 use csv::ReaderBuilder;
-use petgraph::data::Build;
+use petgraph::graphmap::GraphMap;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
 use std::path::PathBuf;
 
 const ALPHA_LENGTH: usize = (b'Z' - b'A') as usize + 1 + 3;
@@ -47,22 +53,148 @@ const AA_DELETE: usize = ALPHA_LENGTH - 3;
 const AA_STOP: usize = ALPHA_LENGTH - 2;
 const AA_ELSE: usize = ALPHA_LENGTH - 1;
 
-fn read_records(amino_acid_sequence: &mut Vec<Vec<u8>>, has_header: bool) {
-    let mut rdr = ReaderBuilder::new()
-        .delimiter(b'\t')
-        .from_reader(io::stdin());
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    Tsv,
+    Fasta,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Dot,
+    Gexf,
+}
+
+// Which statistic measures how strongly two substitutions co-occur.
+// `Support` is the plain co-occurrence frequency used historically; `Jaccard`
+// and `Phi` reward pairs that co-occur far more than chance would predict,
+// which is the real signal for convergent evolution.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Association {
+    Support,
+    Jaccard,
+    Phi,
+}
+
+// The raw co-occurrence count alongside the chosen association score; the
+// graph's edge weight is the association score, but the raw support is kept
+// around since downstream output formats surface both.
+#[derive(Copy, Clone, Debug)]
+struct EdgeWeight {
+    support: u32,
+    association: f32,
+}
+
+fn association_score(metric: Association, c: u32, n_a: u32, n_b: u32, n: u32) -> f32 {
+    match metric {
+        Association::Support => c as f32 / n as f32,
+        Association::Jaccard => {
+            let denom = n_a + n_b - c;
+            if denom == 0 {
+                0.0
+            } else {
+                c as f32 / denom as f32
+            }
+        }
+        Association::Phi => {
+            // 2x2 contingency table: co-occur, A-only, B-only, neither.
+            let c = c as f64;
+            let a_only = n_a as f64 - c;
+            let b_only = n_b as f64 - c;
+            let neither = n as f64 - n_a as f64 - n_b as f64 + c;
+            let denom = (c + a_only) * (b_only + neither) * (c + b_only) * (a_only + neither);
+            if denom <= 0.0 {
+                0.0
+            } else {
+                ((c * neither - a_only * b_only) / denom.sqrt()) as f32
+            }
+        }
+    }
+}
+
+// Folds one thread-local count map into another by summing overlapping keys,
+// used to merge the per-sequence maps rayon produces back into one map.
+fn merge_counts<K: std::hash::Hash + Eq>(
+    mut a: HashMap<K, u32>,
+    b: HashMap<K, u32>,
+) -> HashMap<K, u32> {
+    for (k, v) in b {
+        *a.entry(k).or_insert(0) += v;
+    }
+    a
+}
+
+// Opens stdin or a positional file path and transparently decompresses it
+// (gzip, bzip2, xz, ...) by sniffing the leading magic bytes.
+fn open_input(input: &Option<PathBuf>) -> Result<Box<dyn Read>> {
+    let raw: Box<dyn Read> = match input {
+        Some(path) => Box::new(
+            File::open(path)
+                .with_context(|| format!("Could not open input file {}", path.display()))?,
+        ),
+        None => Box::new(io::stdin()),
+    };
+
+    let (reader, _format) =
+        niffler::get_reader(raw).context("Could not detect input compression")?;
+    Ok(reader)
+}
+
+fn read_records(
+    amino_acid_sequence: &mut Vec<Vec<u8>>,
+    has_header: bool,
+    input_format: InputFormat,
+    input: &Option<PathBuf>,
+) -> Result<()> {
+    let source = input
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "<stdin>".to_string());
+    let reader = open_input(input)?;
+    match input_format {
+        InputFormat::Tsv => read_tsv_records(amino_acid_sequence, has_header, &source, reader),
+        InputFormat::Fasta => read_fasta_records(amino_acid_sequence, &source, reader),
+    }
+}
+
+fn read_tsv_records(
+    amino_acid_sequence: &mut Vec<Vec<u8>>,
+    has_header: bool,
+    source: &str,
+    reader: Box<dyn Read>,
+) -> Result<()> {
+    let mut rdr = ReaderBuilder::new().delimiter(b'\t').from_reader(reader);
 
     if has_header {
-        let _ = rdr.headers().expect("Input file is missing headers!\n");
+        rdr.headers()
+            .with_context(|| format!("Input file {source} is missing headers!"))?;
     }
 
-    for result in rdr.deserialize() {
-        let record: Result<Record, _> = result;
-        match record {
-            Ok(r) => amino_acid_sequence.push(r.aa_aln.as_bytes().to_vec()),
-            Err(e) => panic!("{}", e),
-        }
+    for (i, result) in rdr.deserialize().enumerate() {
+        let record: Record =
+            result.with_context(|| format!("Malformed TSV record at row {} in {source}", i + 1))?;
+        amino_acid_sequence.push(record.aa_aln.as_bytes().to_vec());
     }
+
+    Ok(())
+}
+
+// Aligned multi-FASTA input: the TSV-specific metadata columns don't apply here,
+// only the record's sequence is kept; the record id is logged at trace level
+// for diagnostics since nothing downstream consumes it as a label yet.
+fn read_fasta_records(
+    amino_acid_sequence: &mut Vec<Vec<u8>>,
+    source: &str,
+    reader: Box<dyn Read>,
+) -> Result<()> {
+    let fasta_reader = fasta::Reader::new(reader);
+    for (i, result) in fasta_reader.records().enumerate() {
+        let record =
+            result.with_context(|| format!("Malformed FASTA record at index {i} in {source}"))?;
+        log::trace!("Read FASTA record {}: {}", i, record.id());
+        amino_acid_sequence.push(record.seq().to_vec());
+    }
+    Ok(())
 }
 
 #[derive(Deserialize)]
@@ -95,14 +227,21 @@ struct Cli {
         value_name = "minimum co-ocurrence support"
     )]
     minimum_coocurrence_support: u32,
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = Association::Support,
+        value_name = "association metric"
+    )]
+    association: Association,
     #[clap(
         default_value_t = 0.10,
         long,
         short = 'f',
         value_parser,
-        value_name = "minimum co-occrrence frequency"
+        value_name = "minimum association score"
     )]
-    minimum_cooccrrence_frequency: f32,
+    association_threshold: f32,
     #[clap(
         default_value_t = 0.97,
         long,
@@ -113,20 +252,67 @@ struct Cli {
     conservation_threshold: f32,
     #[clap(short, long, short, action)]
     query_has_header: bool,
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = InputFormat::Tsv,
+        value_name = "input format"
+    )]
+    input_format: InputFormat,
+    #[clap(value_parser, value_name = "Input File")]
+    input: Option<PathBuf>,
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Dot,
+        value_name = "output format"
+    )]
+    output_format: OutputFormat,
+    #[clap(default_value_t = 0, long, value_parser, value_name = "threads")]
+    threads: usize,
+    #[clap(
+        short,
+        long,
+        action = clap::ArgAction::Count,
+        help = "Increase logging verbosity (-v for info, -vv for debug, -vvv for trace)"
+    )]
+    verbose: u8,
 }
 
-fn main() {
+fn main() -> Result<()> {
     let args = Cli::parse();
 
+    let log_level = match args.verbose {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Info,
+        2 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    env_logger::Builder::new().filter_level(log_level).init();
+
+    if args.threads > 0 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(args.threads)
+            .build_global()
+            .context("Could not configure rayon thread pool")?;
+    }
+
     // MT019531 / WUHAN
-    let ref_sequence = std::fs::read_to_string(args.reference_file).expect("Bad reference file");
+    let ref_sequence = std::fs::read_to_string(&args.reference_file)
+        .with_context(|| format!("Bad reference file {}", args.reference_file.display()))?;
     let ref_sequence = ref_sequence.as_bytes();
     let minimum_coocurrence_support = args.minimum_coocurrence_support;
-    let minimum_cooccrrence_frequency: f32 = args.minimum_cooccrrence_frequency;
+    let association = args.association;
+    let association_threshold = args.association_threshold;
     let conservation_threshold: f32 = args.conservation_threshold;
 
     let mut sequences: Vec<Vec<u8>> = vec![];
-    read_records(&mut sequences, args.query_has_header);
+    read_records(
+        &mut sequences,
+        args.query_has_header,
+        args.input_format,
+        &args.input,
+    )?;
 
     // Maximum length of sequences, should be uniform but this is required to avoid issues
     let seq_len = sequences
@@ -136,22 +322,38 @@ fn main() {
         .unwrap_or(0 as usize);
 
     let number_sequences = sequences.len();
-    eprintln!("Data are {number_sequences} x {seq_len}");
-
-    // Amino acid count table
-    let mut counts: Vec<[u32; ALPHA_LENGTH]> = vec![[0u32; ALPHA_LENGTH]; seq_len];
-
-    for s in sequences.iter() {
-        for (i, b) in s.iter().enumerate() {
-            match b {
-                b'A'..=b'Z' => counts[i][(b - b'A') as usize] += 1,
-                b'a'..=b'z' => counts[i][(b - b'a') as usize] += 1,
-                b'-' => counts[i][AA_DELETE] += 1,
-                b'*' => counts[i][AA_STOP] += 1,
-                _ => counts[i][AA_ELSE] += 1,
+    log::info!("Data are {number_sequences} x {seq_len}");
+
+    use rayon::prelude::*;
+
+    // Amino acid count table, tallied per-sequence in parallel and reduced
+    // into the global table by element-wise summation.
+    let counts: Vec<[u32; ALPHA_LENGTH]> = sequences
+        .par_iter()
+        .map(|s| {
+            let mut local = vec![[0u32; ALPHA_LENGTH]; seq_len];
+            for (i, b) in s.iter().enumerate() {
+                match b {
+                    b'A'..=b'Z' => local[i][(b - b'A') as usize] += 1,
+                    b'a'..=b'z' => local[i][(b - b'a') as usize] += 1,
+                    b'-' => local[i][AA_DELETE] += 1,
+                    b'*' => local[i][AA_STOP] += 1,
+                    _ => local[i][AA_ELSE] += 1,
+                }
             }
-        }
-    }
+            local
+        })
+        .reduce(
+            || vec![[0u32; ALPHA_LENGTH]; seq_len],
+            |mut a, b| {
+                for (ai, bi) in a.iter_mut().zip(b.iter()) {
+                    for k in 0..ALPHA_LENGTH {
+                        ai[k] += bi[k];
+                    }
+                }
+                a
+            },
+        );
 
     // Calculate conservation and filter
     let mut valid_positions: Vec<usize> = vec![];
@@ -175,55 +377,79 @@ fn main() {
             if freq < conservation_threshold {
                 valid_positions.push(i);
                 let p = i + 1;
-                eprintln!("{p:0>4} / {aa}: {freq:.4} ({sum})");
+                log::debug!("{p:0>4} / {aa}: {freq:.4} ({sum})");
             }
         }
     }
 
     use itertools::Itertools;
-    use petgraph::graphmap::GraphMap;
-
-    // Add nodes and edges to graph
-    let mut aa_mut_net: GraphMap<NodeSub, u32, petgraph::Undirected> = GraphMap::new();
-    for s in sequences {
-        let mut nodes: Vec<NodeSub> = Vec::new();
-        for ptr in valid_positions.iter() {
-            let i = *ptr;
-            if i >= s.len() || i >= ref_sequence.len() {
-                continue;
-            }
 
-            if ref_sequence[i] != s[i] {
-                let aa_sub = NodeSub::new(i, ref_sequence[i], s[i]);
-                aa_mut_net.add_node(aa_sub);
-                nodes.push(aa_sub);
-            }
-        }
+    // GraphMap isn't Sync, so accumulate co-occurrence counts and per-node
+    // sequence counts per-sequence in parallel into thread-local maps, reduce
+    // them into merged maps, and only then build the GraphMap serially.
+    let (merged_edges, merged_node_counts): (
+        HashMap<(NodeSub, NodeSub), u32>,
+        HashMap<NodeSub, u32>,
+    ) = sequences
+        .par_iter()
+        .map(|s| {
+            let mut nodes: Vec<NodeSub> = Vec::new();
+            for ptr in valid_positions.iter() {
+                let i = *ptr;
+                if i >= s.len() || i >= ref_sequence.len() {
+                    continue;
+                }
 
-        nodes
-            .iter()
-            .tuple_combinations::<(_, _)>()
-            .for_each(|(a, b)| {
-                if aa_mut_net.contains_edge(*a, *b) {
-                    let e = *aa_mut_net.edge_weight(*a, *b).unwrap();
-                    aa_mut_net.update_edge(*a, *b, e + 1);
-                } else {
-                    aa_mut_net.add_edge(*a, *b, 1_u32);
+                if ref_sequence[i] != s[i] {
+                    nodes.push(NodeSub::new(i, ref_sequence[i], s[i]));
                 }
-            });
+            }
+
+            let mut local_edges: HashMap<(NodeSub, NodeSub), u32> = HashMap::new();
+            let mut local_counts: HashMap<NodeSub, u32> = HashMap::new();
+            for node in nodes.iter() {
+                *local_counts.entry(*node).or_insert(0) += 1;
+            }
+            nodes
+                .iter()
+                .tuple_combinations::<(_, _)>()
+                .for_each(|(a, b)| {
+                    *local_edges.entry((*a, *b)).or_insert(0) += 1;
+                });
+            (local_edges, local_counts)
+        })
+        .reduce(
+            || (HashMap::new(), HashMap::new()),
+            |a, b| (merge_counts(a.0, b.0), merge_counts(a.1, b.1)),
+        );
+
+    // Add nodes and edges to graph, scoring each edge by the chosen
+    // association metric
+    let mut aa_mut_net: GraphMap<NodeSub, EdgeWeight, petgraph::Undirected> = GraphMap::new();
+    for ((a, b), support) in merged_edges {
+        let n_a = *merged_node_counts.get(&a).unwrap_or(&0);
+        let n_b = *merged_node_counts.get(&b).unwrap_or(&0);
+        let score = association_score(association, support, n_a, n_b, number_sequences as u32);
+        aa_mut_net.add_edge(
+            a,
+            b,
+            EdgeWeight {
+                support,
+                association: score,
+            },
+        );
     }
 
-    // remove nodes with lack of support or frequency
+    // remove edges with lack of support or association score
     let nodes: Vec<NodeSub> = aa_mut_net.nodes().collect();
     for node in nodes.iter() {
-        let edges: Vec<(NodeSub, NodeSub, u32)> = aa_mut_net
+        let edges: Vec<(NodeSub, NodeSub, EdgeWeight)> = aa_mut_net
             .edges(*node)
             .map(|(a, b, w)| (a, b, *w))
             .collect();
 
         for (a, b, w) in edges {
-            let f = w as f32 / number_sequences as f32;
-            if w < minimum_coocurrence_support || f < minimum_cooccrrence_frequency {
+            if w.support < minimum_coocurrence_support || w.association < association_threshold {
                 aa_mut_net.remove_edge(a, b);
             }
         }
@@ -238,23 +464,128 @@ fn main() {
     }
 
     // print results
-    use petgraph::dot::Dot;
-    use regex::Regex;
-
-    // hack output: TO-DO, improve
-    let re = Regex::new(r#"--\s*\d+\s*\[\s*label\s*=\s*"(\d+)""#).unwrap();
-    let dot = format!("{:?}", Dot::new(&aa_mut_net));
-    let dot = re.replace_all(&dot, "$0, weight=$1");
-    println!("{dot}");
-
-    /*
-        let dot = re.replace_all(&dot, |caps: &Captures| {
-        format!(
-            "{}, weight={}",
-            &caps[0],
-            caps[1].parse::<f32>().unwrap_or_default() / number_sequences as f32
-        )
-    }); */
+    match args.output_format {
+        OutputFormat::Dot => print_dot(&aa_mut_net),
+        OutputFormat::Gexf => print_gexf(&aa_mut_net),
+    }
+
+    Ok(())
+}
+
+// Escapes a value for a double-quoted DOT string (Graphviz's own `\`/`"`
+// escaping), since node labels echo ancestral/derived residue bytes that
+// come straight from caller-supplied sequence input. Known limitation: this
+// does not escape control bytes (e.g. a literal newline), which DOT also
+// can't take unescaped inside a quoted string; the only caller passes
+// substitution labels such as `N501Y`, whose two residue bytes are taken
+// from the input as-is.
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Escapes a value for XML text/attribute content (GEXF), for the same reason.
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+// Native Graphviz DOT output; the edge weight is emitted as a real `weight`
+// attribute instead of being regex-patched onto the label after the fact.
+fn print_dot(graph: &GraphMap<NodeSub, EdgeWeight, petgraph::Undirected>) {
+    use petgraph::dot::{Config, Dot};
+    use petgraph::visit::EdgeRef;
+
+    let dot = Dot::with_attr_getters(
+        graph,
+        &[Config::EdgeNoLabel, Config::NodeNoLabel],
+        &|_, edge| {
+            let w = edge.weight();
+            format!(
+                "label=\"{} ({:.3})\", weight={}",
+                w.support, w.association, w.association
+            )
+        },
+        &|_, (_, node)| format!("label=\"{}\"", escape_dot(&node.to_string())),
+    );
+    println!("{dot:?}");
+}
+
+// Gephi's native GEXF XML: every node keeps its alignment position and
+// ancestral/derived residues as typed attributes, and every edge keeps both
+// the raw co-occurrence support and the chosen association score, so Gephi
+// users get filterable columns instead of a single mangled DOT label.
+fn print_gexf(graph: &GraphMap<NodeSub, EdgeWeight, petgraph::Undirected>) {
+    use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+    use std::fmt::Write as _;
+
+    let node_ids: HashMap<NodeSub, usize> = graph
+        .nodes()
+        .enumerate()
+        .map(|(id, node)| (node, id))
+        .collect();
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    xml.push_str("  <graph mode=\"static\" defaultedgetype=\"undirected\">\n");
+    xml.push_str("    <attributes class=\"node\">\n");
+    xml.push_str("      <attribute id=\"0\" title=\"position\" type=\"integer\"/>\n");
+    xml.push_str("      <attribute id=\"1\" title=\"ancestral\" type=\"string\"/>\n");
+    xml.push_str("      <attribute id=\"2\" title=\"derived\" type=\"string\"/>\n");
+    xml.push_str("    </attributes>\n");
+    xml.push_str("    <attributes class=\"edge\">\n");
+    xml.push_str("      <attribute id=\"0\" title=\"support\" type=\"integer\"/>\n");
+    xml.push_str("      <attribute id=\"1\" title=\"association\" type=\"float\"/>\n");
+    xml.push_str("    </attributes>\n");
+
+    xml.push_str("    <nodes>\n");
+    for (node, id) in &node_ids {
+        let position = node.index + 1;
+        let label = escape_xml(&node.to_string());
+        let ancestral = escape_xml(&(node.ancestral as char).to_string());
+        let derived = escape_xml(&(node.derived as char).to_string());
+        let _ = writeln!(
+            xml,
+            "      <node id=\"{id}\" label=\"{label}\">\n        \
+             <attvalues>\n          \
+             <attvalue for=\"0\" value=\"{position}\"/>\n          \
+             <attvalue for=\"1\" value=\"{ancestral}\"/>\n          \
+             <attvalue for=\"2\" value=\"{derived}\"/>\n        \
+             </attvalues>\n      </node>",
+        );
+    }
+    xml.push_str("    </nodes>\n");
+
+    xml.push_str("    <edges>\n");
+    for (edge_id, edge) in graph.edge_references().enumerate() {
+        let w = edge.weight();
+        let source = node_ids[&edge.source()];
+        let target = node_ids[&edge.target()];
+        let _ = writeln!(
+            xml,
+            "      <edge id=\"{edge_id}\" source=\"{source}\" target=\"{target}\" weight=\"{}\">\n        \
+             <attvalues>\n          \
+             <attvalue for=\"0\" value=\"{}\"/>\n          \
+             <attvalue for=\"1\" value=\"{}\"/>\n        \
+             </attvalues>\n      </edge>",
+            w.association, w.support, w.association
+        );
+    }
+    xml.push_str("    </edges>\n");
+
+    xml.push_str("  </graph>\n");
+    xml.push_str("</gexf>");
+    println!("{xml}");
 }
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Ord, Eq, Hash)]
@@ -306,3 +637,93 @@ fn index_to_aa(index: usize) -> char {
         _ => (index as u8 + b'A') as char,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn association_score_support_is_raw_frequency() {
+        let score = association_score(Association::Support, 5, 0, 0, 20);
+        assert_eq!(score, 0.25);
+    }
+
+    #[test]
+    fn association_score_jaccard_divides_by_union() {
+        let score = association_score(Association::Jaccard, 3, 5, 4, 0);
+        assert_eq!(score, 0.5);
+    }
+
+    #[test]
+    fn association_score_jaccard_handles_zero_union() {
+        let score = association_score(Association::Jaccard, 3, 2, 1, 0);
+        assert_eq!(score, 0.0);
+    }
+
+    #[test]
+    fn association_score_phi_is_one_for_perfect_overlap() {
+        // n_a == n_b == c: A and B always co-occur.
+        let score = association_score(Association::Phi, 5, 5, 5, 10);
+        assert!((score - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn association_score_phi_is_zero_under_independence() {
+        // c matches the count expected if A and B were independent (n_a*n_b/n).
+        let score = association_score(Association::Phi, 5, 10, 10, 20);
+        assert!(score.abs() < 1e-6);
+    }
+
+    #[test]
+    fn association_score_phi_is_negative_one_for_mutual_exclusion() {
+        let score = association_score(Association::Phi, 0, 5, 5, 10);
+        assert!((score + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn merge_counts_sums_overlapping_keys() {
+        let mut a = HashMap::new();
+        a.insert("x", 2);
+        a.insert("y", 1);
+        let mut b = HashMap::new();
+        b.insert("x", 3);
+        b.insert("z", 4);
+
+        let merged = merge_counts(a, b);
+
+        assert_eq!(merged.get("x"), Some(&5));
+        assert_eq!(merged.get("y"), Some(&1));
+        assert_eq!(merged.get("z"), Some(&4));
+        assert_eq!(merged.len(), 3);
+    }
+
+    #[test]
+    fn merge_counts_with_empty_map_is_identity() {
+        let mut a = HashMap::new();
+        a.insert("x", 2);
+
+        let merged = merge_counts(a.clone(), HashMap::new());
+
+        assert_eq!(merged, a);
+    }
+
+    #[test]
+    fn escape_dot_escapes_backslash_and_quote() {
+        assert_eq!(escape_dot(r#"a\b"c"#), r#"a\\b\"c"#);
+    }
+
+    #[test]
+    fn escape_dot_leaves_plain_text_untouched() {
+        assert_eq!(escape_dot("N501Y"), "N501Y");
+    }
+
+    #[test]
+    fn escape_xml_escapes_all_special_characters() {
+        assert_eq!(escape_xml(r#"&<>"'"#), "&amp;&lt;&gt;&quot;&apos;");
+    }
+
+    #[test]
+    fn escape_xml_leaves_plain_text_untouched() {
+        assert_eq!(escape_xml("N501Y"), "N501Y");
+    }
+}